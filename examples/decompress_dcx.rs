@@ -15,7 +15,7 @@ fn main() {
 
     let fname = std::path::Path::new(&*args[1]);
     let output_dir = std::path::Path::new(&*args[2]);
-    let file = fs::File::open(&fname).unwrap();
+    let file = fs::File::open(fname).unwrap();
 
     let mut dcx_reader = DcxReader::new(BufReader::new(file)).unwrap();
     let mut output = Vec::new();
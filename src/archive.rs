@@ -0,0 +1,2 @@
+pub mod bnd4;
+pub use bnd4::*;
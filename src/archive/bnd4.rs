@@ -1,13 +1,16 @@
 use std::io;
-use std::io::{Error, ErrorKind, Read, Seek, SeekFrom};
+use std::io::{Error as IoError, Read, Seek, SeekFrom, Write};
 use std::ops::Deref;
 
 use bitflags::bitflags;
 use byteorder::{BigEndian, ReadBytesExt};
 use byteorder::{ByteOrder, LittleEndian};
 
-use crate::io::{invalid_data, ReadExt, SeekableReadExt};
-use crate::DcxReader;
+use crate::io::{invalid_data, not_found, ReadExt, SeekableReadExt, VerifyingReader, WriteExt};
+use crate::{DcxReader, DcxWriter, Error};
+
+#[cfg(feature = "async")]
+use crate::io::decode_name;
 
 bitflags! {
     #[repr(C)]
@@ -22,7 +25,7 @@ bitflags! {
 
 bitflags! {
     #[repr(C)]
-    struct Bnd4Flags : u8 {
+    pub struct Bnd4Flags : u8 {
         /// File is big-endian regardless of the big-endian byte.
         const BIG_ENDIAN = 0b0000_0001;
 
@@ -66,6 +69,9 @@ pub struct Bnd4Archive<R: Read + Seek> {
     reader: R,
 }
 
+// Several fields here are parsed for completeness but have no accessor yet;
+// keeping them on the struct means adding one later is a non-breaking change.
+#[allow(dead_code)]
 pub struct Bnd4ArchiveInfo {
     file_count: u32,
     header_size: u64,
@@ -92,7 +98,7 @@ impl<R: Read + Seek> Bnd4Archive<R> {
     fn read_archive_info<Order: ByteOrder>(
         reader: &mut R,
         is_flags_le: bool,
-    ) -> Result<Bnd4ArchiveInfo, std::io::Error> {
+    ) -> Result<Bnd4ArchiveInfo, Error> {
         let file_count = reader.read_u32::<Order>()?;
         let header_size = reader.read_u64::<Order>()?;
 
@@ -182,9 +188,12 @@ impl<R: Read + Seek> Bnd4Archive<R> {
         })
     }
 
-    pub fn file(&mut self, index: usize) -> Result<Bnd4File, Error> {
+    pub fn file(&mut self, index: usize) -> Result<Bnd4File<'_>, Error> {
         if index >= self.archive_info.file_count as usize {
-            panic!(); // @TODO: error
+            return Err(Error::IndexOutOfRange {
+                index,
+                len: self.archive_info.file_count as usize,
+            });
         }
 
         let is_big_endian_str = self.is_big_endian;
@@ -192,23 +201,21 @@ impl<R: Read + Seek> Bnd4Archive<R> {
 
         let entry = &self.entries[index];
         let name = match entry.name_offset {
-            Some(offset) => Some(self.reader.at(offset as u64, |r| {
-                if is_unicode {
-                    r.read_utf16(is_big_endian_str)
-                } else {
-                    r.read_cstr()
-                }
-            })?),
+            Some(offset) => Some(
+                self.reader
+                    .at(offset as u64, |r| r.read_name(is_unicode, is_big_endian_str))?,
+            ),
             None => None,
         };
 
         self.reader.seek(SeekFrom::Start(entry.data_offset))?;
 
+        let expected_size = entry.decompressed_size.unwrap_or(entry.size);
         let data = (&mut self.reader as &mut dyn Read).take(entry.size);
         let reader = if entry.flags.contains(Bnd4EntryFlags::COMPRESSED) {
-            Bnd4FileReader::Compressed(DcxReader::new(data)?)
+            Bnd4FileReader::Compressed(VerifyingReader::new(DcxReader::new(data)?, expected_size))
         } else {
-            Bnd4FileReader::Uncompressed(data)
+            Bnd4FileReader::Uncompressed(VerifyingReader::new(data, expected_size))
         };
 
         Ok(Bnd4File {
@@ -223,6 +230,124 @@ impl<R: Read + Seek> Bnd4Archive<R> {
         self.archive_info.file_count as usize
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.archive_info.file_count == 0
+    }
+
+    /// Looks up a file by its archive path. Uses the on-disk name-bucket hash
+    /// table when the archive has one, falling back to a linear scan of every
+    /// entry's name otherwise.
+    pub fn file_by_name(&mut self, path: &str) -> Result<Bnd4File<'_>, Error> {
+        let index = if self.archive_info.name_buckets_offset != 0 {
+            self.find_by_name_bucket(path)?
+        } else {
+            self.find_by_linear_scan(path)?
+        };
+
+        match index {
+            Some(index) => self.file(index),
+            None => Err(not_found(&format!("No file named \"{}\" in archive", path))),
+        }
+    }
+
+    fn find_by_linear_scan(&mut self, path: &str) -> Result<Option<usize>, Error> {
+        let is_big_endian_str = self.is_big_endian;
+        let is_unicode = self.unicode;
+        let path = normalize_path(path);
+
+        for index in 0..self.entries.len() {
+            let name_offset = match self.entries[index].name_offset {
+                Some(offset) => offset,
+                None => continue,
+            };
+
+            let name = self
+                .reader
+                .at(name_offset as u64, |r| r.read_name(is_unicode, is_big_endian_str))?;
+
+            if normalize_path(&name) == path {
+                return Ok(Some(index));
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn find_by_name_bucket(&mut self, path: &str) -> Result<Option<usize>, Error> {
+        if self.is_big_endian {
+            self.find_by_name_bucket_ordered::<BigEndian>(path)
+        } else {
+            self.find_by_name_bucket_ordered::<LittleEndian>(path)
+        }
+    }
+
+    /// Resolves a path to a file index using the bucket table at
+    /// `name_buckets_offset`: a bucket count, then per-bucket
+    /// `(entry_count, entry_index_offset)` pairs, each pointing to a list of
+    /// `(hash, file_index)` entries.
+    ///
+    /// A hash match only narrows down a candidate; since `hash_path` folds
+    /// every path down to 32 bits, two different paths can collide. Each
+    /// candidate's actual name is read back and compared (the same
+    /// normalization `find_by_linear_scan` uses) before it's accepted, so a
+    /// collision falls through to the next entry in the bucket instead of
+    /// returning the wrong file.
+    fn find_by_name_bucket_ordered<O: ByteOrder>(
+        &mut self,
+        path: &str,
+    ) -> Result<Option<usize>, Error> {
+        let hash = hash_path(path);
+        let path = normalize_path(path);
+        let buckets_offset = self.archive_info.name_buckets_offset;
+
+        let bucket_count = self.reader.at(buckets_offset, |r| Ok(r.read_u32::<O>()?))?;
+        if bucket_count == 0 {
+            return Ok(None);
+        }
+
+        let bucket_index = (hash % bucket_count) as u64;
+        let bucket_header_offset = buckets_offset + 4 + bucket_index * 12;
+
+        let (entry_count, entries_offset) = self.reader.at(bucket_header_offset, |r| {
+            let entry_count = r.read_u32::<O>()?;
+            let entries_offset = r.read_u64::<O>()?;
+            Ok((entry_count, entries_offset))
+        })?;
+
+        let is_big_endian_str = self.is_big_endian;
+        let is_unicode = self.unicode;
+
+        for slot in 0..entry_count {
+            let (entry_hash, entry_index) = self
+                .reader
+                .at(entries_offset + slot as u64 * 8, |r| {
+                    let entry_hash = r.read_u32::<O>()?;
+                    let entry_index = r.read_u32::<O>()?;
+                    Ok((entry_hash, entry_index))
+                })?;
+
+            if entry_hash != hash {
+                continue;
+            }
+
+            let entry_index = entry_index as usize;
+            let name_offset = match self.entries.get(entry_index).and_then(|e| e.name_offset) {
+                Some(offset) => offset,
+                None => continue,
+            };
+
+            let name = self
+                .reader
+                .at(name_offset as u64, |r| r.read_name(is_unicode, is_big_endian_str))?;
+
+            if normalize_path(&name) == path {
+                return Ok(Some(entry_index));
+            }
+        }
+
+        Ok(None)
+    }
+
     /// Read the archive information and file listing using the byte order specified in the archive header.
     fn new_from_header<O: ByteOrder>(
         reader: &mut R,
@@ -241,8 +366,8 @@ impl<R: Read + Seek> Bnd4Archive<R> {
         Ok((archive_info, entries))
     }
 
-    pub fn new(mut reader: R) -> Result<Self, std::io::Error> {
-        reader.expect(b"BND4")?;
+    pub fn new(mut reader: R) -> Result<Self, Error> {
+        reader.expect_magic_at_offset(b"BND4")?;
 
         // unk04
         let _ = reader.read_bool()?;
@@ -273,6 +398,9 @@ impl<R: Read + Seek> Bnd4Archive<R> {
 }
 
 pub struct Bnd4File<'a> {
+    // Kept so accessors for archive-wide metadata (unicode/endianness/etc.)
+    // can be added to `Bnd4File` without another lookup through the archive.
+    #[allow(dead_code)]
     archive: &'a Bnd4ArchiveInfo,
     entry: &'a Bnd4FileInfo,
     reader: Bnd4FileReader<'a>,
@@ -284,19 +412,29 @@ pub struct Bnd4FileInfo {
     size: u64,
     data_offset: u64,
     decompressed_size: Option<u64>,
+    // Not surfaced yet; kept for when file-by-id lookup is added.
+    #[allow(dead_code)]
     id: Option<i32>,
     name_offset: Option<u32>,
 }
 
 enum Bnd4FileReader<'archive> {
-    Uncompressed(io::Take<&'archive mut dyn Read>),
-    Compressed(DcxReader<io::Take<&'archive mut dyn Read>>),
+    Uncompressed(VerifyingReader<io::Take<&'archive mut dyn Read>>),
+    Compressed(VerifyingReader<DcxReader<io::Take<&'archive mut dyn Read>>>),
 }
 
 impl<'archive> Bnd4File<'archive> {
     pub fn name(&self) -> Option<&str> {
         self.name.as_deref()
     }
+
+    #[cfg(feature = "checksum")]
+    pub fn checksum(&self) -> u32 {
+        match &self.reader {
+            Bnd4FileReader::Uncompressed(r) => r.checksum(),
+            Bnd4FileReader::Compressed(r) => r.checksum(),
+        }
+    }
 }
 
 impl<'archive> Deref for Bnd4File<'archive> {
@@ -308,10 +446,667 @@ impl<'archive> Deref for Bnd4File<'archive> {
 }
 
 impl<'archive> Read for Bnd4File<'archive> {
-    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, IoError> {
         match &mut self.reader {
             Bnd4FileReader::Compressed(ref mut r) => r.read(buf),
             Bnd4FileReader::Uncompressed(ref mut r) => r.read(buf),
         }
     }
 }
+
+fn align_up(value: u64, alignment: u64) -> u64 {
+    value.div_ceil(alignment) * alignment
+}
+
+/// FromSoftware path comparison is case-insensitive and doesn't care about
+/// slash direction; both `hash_path` and the exact name comparisons in
+/// `find_by_linear_scan`/`find_by_name_bucket_ordered` normalize through this
+/// so the two lookup strategies agree on what "the same path" means.
+fn normalize_path(path: &str) -> String {
+    path.to_lowercase().replace('\\', "/")
+}
+
+/// FromSoftware's path hash: normalize the path, then fold
+/// `hash = hash * 37 + byte` over it.
+fn hash_path(path: &str) -> u32 {
+    normalize_path(path)
+        .bytes()
+        .fold(0u32, |hash, byte| hash.wrapping_mul(37).wrapping_add(byte as u32))
+}
+
+const ARCHIVE_HEADER_SIZE: u64 = 0x40;
+const DATA_ALIGNMENT: u64 = 0x10;
+
+/// On-disk size of the archive info block read by [`Bnd4Archive::read_archive_info`]:
+/// file_count(4) + header_size(8) + version(8) + file_header_size(8) +
+/// file_header_end(8) + unicode(1) + format(1) + extended(1) + padding(1) +
+/// unk(4) + name_buckets_offset(8).
+#[cfg(feature = "async")]
+const ARCHIVE_INFO_SIZE: u64 = 52;
+
+/// Size in bytes of one entry header for the given format flags, mirroring
+/// the field layout [`Bnd4Archive::read_entry_info`] reads and
+/// [`Bnd4Writer::write_entry_header`] writes.
+fn entry_header_size(format: Bnd4Flags) -> u64 {
+    let mut size = 1 + 3 + 4 + 8; // flags + padding + -1 marker + size
+    if format.contains(Bnd4Flags::SUPPORTS_COMPRESSION) {
+        size += 8;
+    }
+    size += if format.has_long_offsets() { 8 } else { 4 };
+    if format.supports_ids() {
+        size += 4;
+    }
+    if format.supports_filenames() {
+        size += 4;
+    }
+    size
+}
+
+/// A file to be written into a [`Bnd4Writer`]. `data` is always the original,
+/// uncompressed bytes; `compressed` decides whether they're re-wrapped
+/// through [`DcxWriter`] before being laid out on disk.
+pub struct Bnd4WriterEntry {
+    pub id: Option<i32>,
+    pub name: Option<String>,
+    pub data: Vec<u8>,
+    pub compressed: bool,
+}
+
+/// Writes BND4 archives, the inverse of [`Bnd4Archive`].
+///
+/// Entries are buffered and the whole header/data/name-table layout is
+/// computed in two passes once [`Bnd4Writer::finish`] is called, since entry
+/// headers need the data and name offsets of every entry up front.
+pub struct Bnd4Writer<W: Write + Seek> {
+    writer: W,
+    format: Bnd4Flags,
+    is_big_endian: bool,
+    unicode: bool,
+    entries: Vec<Bnd4WriterEntry>,
+}
+
+impl<W: Write + Seek> Bnd4Writer<W> {
+    pub fn new(writer: W, format: Bnd4Flags, is_big_endian: bool, unicode: bool) -> Self {
+        Self {
+            writer,
+            format,
+            is_big_endian,
+            unicode,
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, entry: Bnd4WriterEntry) {
+        self.entries.push(entry);
+    }
+
+    fn entry_header_size(&self) -> u64 {
+        entry_header_size(self.format)
+    }
+
+    pub fn finish(self) -> Result<W, Error> {
+        if self.is_big_endian {
+            self.write_archive::<BigEndian>()
+        } else {
+            self.write_archive::<LittleEndian>()
+        }
+    }
+
+    fn write_archive<O: ByteOrder>(mut self) -> Result<W, Error> {
+        let entry_header_size = self.entry_header_size();
+        let file_header_end = ARCHIVE_HEADER_SIZE + entry_header_size * self.entries.len() as u64;
+
+        // First pass: compress payloads up front so their on-disk sizes are
+        // known, then lay out the data section and name table that follow
+        // the fixed-size entry headers.
+        let mut payloads = Vec::with_capacity(self.entries.len());
+        let mut name_offsets = Vec::with_capacity(self.entries.len());
+        let mut name_table = Vec::new();
+        let mut cursor = file_header_end;
+
+        for entry in &self.entries {
+            let payload = if entry.compressed {
+                let mut dcx = DcxWriter::new(Vec::new());
+                dcx.write_all(&entry.data)?;
+                dcx.finish()?
+            } else {
+                entry.data.clone()
+            };
+
+            let offset = align_up(cursor, DATA_ALIGNMENT);
+            cursor = offset + payload.len() as u64;
+            payloads.push((offset, payload));
+
+            name_offsets.push(match &entry.name {
+                Some(name) if self.format.supports_filenames() => {
+                    let offset = name_table.len() as u64;
+                    if self.unicode {
+                        name_table.write_utf16(name, self.is_big_endian)?;
+                    } else {
+                        name_table.write_cstr(name)?;
+                    }
+                    Some(offset)
+                }
+                _ => None,
+            });
+        }
+
+        let name_table_offset = cursor;
+
+        self.write_header::<O>(file_header_end, entry_header_size)?;
+
+        for index in 0..self.entries.len() {
+            let (compressed, id, uncompressed_size) = {
+                let entry = &self.entries[index];
+                (entry.compressed, entry.id, entry.data.len() as u64)
+            };
+            let (offset, on_disk_size) = (payloads[index].0, payloads[index].1.len() as u64);
+            let name_offset = name_offsets[index].map(|offset| offset + name_table_offset);
+
+            self.write_entry_header::<O>(
+                compressed,
+                id,
+                uncompressed_size,
+                offset,
+                on_disk_size,
+                name_offset,
+            )?;
+        }
+
+        let header_end_position = self.writer.stream_position()?;
+        debug_assert_eq!(header_end_position, file_header_end);
+
+        let mut position = file_header_end;
+        for (offset, payload) in &payloads {
+            if *offset > position {
+                self.writer
+                    .write_all(&vec![0u8; (*offset - position) as usize])?;
+            }
+            self.writer.write_all(payload)?;
+            position = *offset + payload.len() as u64;
+        }
+
+        self.writer.write_all(&name_table)?;
+
+        Ok(self.writer)
+    }
+
+    fn write_header<O: ByteOrder>(
+        &mut self,
+        file_header_end: u64,
+        entry_header_size: u64,
+    ) -> Result<(), Error> {
+        self.writer.write_all(b"BND4")?;
+        self.writer.write_bool(true)?; // unk04
+        self.writer.write_bool(true)?; // unk05
+        self.writer.write_all(&[0, 0, 0])?;
+        self.writer.write_bool(self.is_big_endian)?;
+        self.writer.write_bool(true)?; // flags are stored in native byte order
+        self.writer.write_all(&[0])?;
+
+        self.writer
+            .write_num::<u32, O>(self.entries.len() as u32)?;
+        self.writer.write_num::<u64, O>(ARCHIVE_HEADER_SIZE)?;
+        self.writer.write_all(&[0u8; 8])?; // version
+        self.writer.write_num::<u64, O>(entry_header_size)?;
+        self.writer.write_num::<u64, O>(file_header_end)?;
+        self.writer.write_bool(self.unicode)?;
+        self.writer.write_all(&[self.format.bits()])?;
+        self.writer.write_bool(false)?; // extended
+        self.writer.write_all(&[0])?;
+        self.writer.write_num::<u32, O>(0)?;
+        // No name-bucket table is emitted; readers fall back to a linear scan.
+        self.writer.write_num::<u64, O>(0)?;
+
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn write_entry_header<O: ByteOrder>(
+        &mut self,
+        compressed: bool,
+        id: Option<i32>,
+        uncompressed_size: u64,
+        data_offset: u64,
+        on_disk_size: u64,
+        name_offset: Option<u64>,
+    ) -> Result<(), Error> {
+        let mut flags = Bnd4EntryFlags::empty();
+        if compressed {
+            flags |= Bnd4EntryFlags::COMPRESSED;
+        }
+        if self.format.supports_ids() && id.is_some() {
+            flags |= Bnd4EntryFlags::HAS_ID;
+        }
+        if name_offset.is_some() {
+            flags |= Bnd4EntryFlags::HAS_NAME;
+        }
+
+        self.writer.write_all(&[flags.bits()])?;
+        self.writer.write_all(&[0, 0, 0])?;
+        self.writer.write_num::<i32, O>(-1)?;
+        self.writer.write_num::<u64, O>(on_disk_size)?;
+
+        if self.format.contains(Bnd4Flags::SUPPORTS_COMPRESSION) {
+            self.writer.write_num::<u64, O>(uncompressed_size)?;
+        }
+
+        if self.format.has_long_offsets() {
+            self.writer.write_num::<u64, O>(data_offset)?;
+        } else {
+            self.writer.write_num::<u32, O>(data_offset as u32)?;
+        }
+
+        if self.format.supports_ids() {
+            self.writer.write_num::<i32, O>(id.unwrap_or(-1))?;
+        }
+
+        if self.format.supports_filenames() {
+            self.writer
+                .write_num::<u32, O>(name_offset.unwrap_or(0) as u32)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async")]
+use std::io::Cursor;
+
+#[cfg(feature = "async")]
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt};
+
+#[cfg(feature = "async")]
+use crate::AsyncDcxReader;
+
+#[cfg(feature = "async")]
+async fn read_bool_async<R: AsyncRead + Unpin>(reader: &mut R) -> Result<bool, Error> {
+    Ok(reader.read_u8().await? == 1)
+}
+
+#[cfg(feature = "async")]
+async fn expect_u8_async<R: AsyncRead + Unpin>(reader: &mut R, expected: u8) -> Result<(), Error> {
+    if reader.read_u8().await? != expected {
+        return Err(invalid_data("Unexpected data"));
+    }
+    Ok(())
+}
+
+#[cfg(feature = "async")]
+async fn expect_async<R: AsyncRead + Unpin>(reader: &mut R, bytes: &[u8]) -> Result<(), Error> {
+    for (pos, byte) in bytes.iter().enumerate() {
+        if reader.read_u8().await? != *byte {
+            return Err(invalid_data(&format!("Unexpected data at position {}", pos)));
+        }
+    }
+    Ok(())
+}
+
+/// Async counterpart to [`Bnd4Archive`], for archives served over a
+/// [`tokio::io::AsyncRead`] + [`tokio::io::AsyncSeek`] source (a network
+/// stream, an async file handle) where buffering the whole decompressed
+/// container into memory up front isn't acceptable. Shares
+/// [`Bnd4ArchiveInfo`]/[`Bnd4FileInfo`] and the `Bnd4Flags`-driven field
+/// layout with the sync reader; only the I/O calls that produce them differ.
+#[cfg(feature = "async")]
+pub struct AsyncBnd4Archive<R: AsyncRead + AsyncSeek + Unpin> {
+    archive_info: Bnd4ArchiveInfo,
+    entries: Vec<Bnd4FileInfo>,
+    is_big_endian: bool,
+    reader: R,
+}
+
+#[cfg(feature = "async")]
+impl<R: AsyncRead + AsyncSeek + Unpin> Deref for AsyncBnd4Archive<R> {
+    type Target = Bnd4ArchiveInfo;
+
+    fn deref(&self) -> &Self::Target {
+        &self.archive_info
+    }
+}
+
+#[cfg(feature = "async")]
+impl<R: AsyncRead + AsyncSeek + Unpin> AsyncBnd4Archive<R> {
+    /// Reads the fixed-size archive info block and parses it by delegating to
+    /// [`Bnd4Archive::read_archive_info`] over an in-memory cursor, so the two
+    /// readers can never drift on field order: this just supplies the bytes.
+    async fn read_archive_info(
+        reader: &mut R,
+        is_big_endian: bool,
+        is_flags_le: bool,
+    ) -> Result<Bnd4ArchiveInfo, Error> {
+        let mut bytes = vec![0u8; ARCHIVE_INFO_SIZE as usize];
+        reader.read_exact(&mut bytes).await?;
+
+        let mut cursor = Cursor::new(bytes.as_slice());
+        if is_big_endian {
+            Bnd4Archive::<Cursor<&[u8]>>::read_archive_info::<BigEndian>(&mut cursor, is_flags_le)
+        } else {
+            Bnd4Archive::<Cursor<&[u8]>>::read_archive_info::<LittleEndian>(&mut cursor, is_flags_le)
+        }
+    }
+
+    /// Reads one fixed-size entry header and parses it by delegating to
+    /// [`Bnd4Archive::read_entry_info`] over an in-memory cursor, for the same
+    /// reason as [`Self::read_archive_info`].
+    async fn read_entry_info(
+        reader: &mut R,
+        format: Bnd4Flags,
+        is_big_endian: bool,
+        is_flags_little_endian: bool,
+    ) -> Result<Bnd4FileInfo, Error> {
+        let mut bytes = vec![0u8; entry_header_size(format) as usize];
+        reader.read_exact(&mut bytes).await?;
+
+        let mut cursor = Cursor::new(bytes.as_slice());
+        if is_big_endian {
+            Bnd4Archive::<Cursor<&[u8]>>::read_entry_info::<BigEndian>(
+                &mut cursor,
+                format,
+                is_flags_little_endian,
+            )
+        } else {
+            Bnd4Archive::<Cursor<&[u8]>>::read_entry_info::<LittleEndian>(
+                &mut cursor,
+                format,
+                is_flags_little_endian,
+            )
+        }
+    }
+
+    pub async fn new(mut reader: R) -> Result<Self, Error> {
+        expect_async(&mut reader, b"BND4").await?;
+
+        let _ = read_bool_async(&mut reader).await?; // unk04
+        let _ = read_bool_async(&mut reader).await?; // unk05
+
+        expect_u8_async(&mut reader, 0).await?;
+        expect_u8_async(&mut reader, 0).await?;
+        expect_u8_async(&mut reader, 0).await?;
+
+        let is_big_endian = read_bool_async(&mut reader).await?;
+        let is_flags_little_endian = read_bool_async(&mut reader).await?;
+        reader.read_u8().await?;
+
+        let archive_info =
+            Self::read_archive_info(&mut reader, is_big_endian, is_flags_little_endian).await?;
+
+        let mut entries = Vec::with_capacity(archive_info.file_count as usize);
+        for _ in 0..archive_info.file_count {
+            let entry_info = Self::read_entry_info(
+                &mut reader,
+                archive_info.format,
+                is_big_endian,
+                is_flags_little_endian,
+            )
+            .await?;
+
+            entries.push(entry_info);
+        }
+
+        Ok(Self {
+            archive_info,
+            is_big_endian,
+            entries,
+            reader,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.archive_info.file_count as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.archive_info.file_count == 0
+    }
+
+    /// Reads a name at the reader's current position, honoring the archive's
+    /// `unicode`/`is_big_endian` flags the same way [`ReadExt::read_cstr`]
+    /// and [`ReadExt::read_utf16`] do for the sync reader.
+    async fn read_name_async(&mut self) -> Result<String, Error> {
+        let is_unicode = self.archive_info.unicode;
+        let is_big_endian = self.is_big_endian;
+
+        let mut data = Vec::new();
+        if is_unicode {
+            loop {
+                let lo = self.reader.read_u8().await?;
+                let hi = self.reader.read_u8().await?;
+                if lo == 0 && hi == 0 {
+                    break;
+                }
+                data.push(lo);
+                data.push(hi);
+            }
+        } else {
+            loop {
+                let byte = self.reader.read_u8().await?;
+                if byte == 0 {
+                    break;
+                }
+                data.push(byte);
+            }
+        }
+
+        Ok(decode_name(&data, is_unicode, is_big_endian))
+    }
+
+    /// Reads one entry's data fully into memory and returns an
+    /// [`AsyncBnd4File`] over it. Unlike the sync [`Bnd4Archive::file`], this
+    /// can't borrow the archive's reader for the lifetime of the returned
+    /// file (tokio's `AsyncSeek`/`AsyncRead` can't be driven concurrently
+    /// from two futures over the same `&mut`), so the entry's raw bytes are
+    /// read up front; this still avoids buffering the whole archive, which
+    /// is the cost this type exists to avoid.
+    pub async fn file(&mut self, index: usize) -> Result<AsyncBnd4File, Error> {
+        if index >= self.archive_info.file_count as usize {
+            return Err(Error::IndexOutOfRange {
+                index,
+                len: self.archive_info.file_count as usize,
+            });
+        }
+
+        let entry = &self.entries[index];
+        let name_offset = entry.name_offset;
+        let data_offset = entry.data_offset;
+        let size = entry.size;
+        let decompressed_size = entry.decompressed_size;
+        let flags = entry.flags;
+
+        let name = match name_offset {
+            Some(offset) => {
+                let current_pos = self.reader.stream_position().await?;
+                self.reader.seek(SeekFrom::Start(offset as u64)).await?;
+                let name = self.read_name_async().await?;
+                self.reader.seek(SeekFrom::Start(current_pos)).await?;
+                Some(name)
+            }
+            None => None,
+        };
+
+        self.reader.seek(SeekFrom::Start(data_offset)).await?;
+        let mut raw = vec![0u8; size as usize];
+        self.reader.read_exact(&mut raw).await?;
+
+        let expected_size = decompressed_size.unwrap_or(size);
+        let reader = if flags.contains(Bnd4EntryFlags::COMPRESSED) {
+            AsyncBnd4FileReader::Compressed(AsyncDcxReader::new(Cursor::new(raw)).await?)
+        } else {
+            AsyncBnd4FileReader::Uncompressed(Cursor::new(raw))
+        };
+
+        Ok(AsyncBnd4File {
+            name,
+            expected_size,
+            bytes_read: 0,
+            reader,
+        })
+    }
+}
+
+#[cfg(feature = "async")]
+enum AsyncBnd4FileReader {
+    Uncompressed(Cursor<Vec<u8>>),
+    Compressed(AsyncDcxReader<Cursor<Vec<u8>>>),
+}
+
+/// A single entry read out of an [`AsyncBnd4Archive`]. Its bytes are already
+/// resident in memory (see [`AsyncBnd4Archive::file`]); `AsyncRead` here just
+/// drives deflate decompression for compressed entries without blocking.
+///
+/// Like [`VerifyingReader`] on the sync side, it tracks the total bytes
+/// produced and, once the inner reader signals EOF, checks that against
+/// `expected_size` so a truncated/corrupt compressed entry surfaces as an
+/// error instead of a silently short read.
+#[cfg(feature = "async")]
+pub struct AsyncBnd4File {
+    name: Option<String>,
+    expected_size: u64,
+    bytes_read: u64,
+    reader: AsyncBnd4FileReader,
+}
+
+#[cfg(feature = "async")]
+impl AsyncBnd4File {
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn expected_size(&self) -> u64 {
+        self.expected_size
+    }
+}
+
+#[cfg(feature = "async")]
+impl AsyncRead for AsyncBnd4File {
+    fn poll_read(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        let wants_more = buf.remaining() > 0;
+        let before = buf.filled().len();
+
+        let poll = match &mut self.reader {
+            AsyncBnd4FileReader::Uncompressed(r) => std::pin::Pin::new(r).poll_read(cx, buf),
+            AsyncBnd4FileReader::Compressed(r) => std::pin::Pin::new(r).poll_read(cx, buf),
+        };
+
+        if let std::task::Poll::Ready(Ok(())) = &poll {
+            let produced = (buf.filled().len() - before) as u64;
+            self.bytes_read += produced;
+
+            if wants_more && produced == 0 && self.bytes_read != self.expected_size {
+                return std::task::Poll::Ready(Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!(
+                        "Decompressed size mismatch: expected {} bytes, got {}",
+                        self.expected_size, self.bytes_read
+                    ),
+                )));
+            }
+        }
+
+        poll
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use byteorder::{LittleEndian, WriteBytesExt};
+
+    use super::*;
+
+    #[test]
+    fn bnd4_writer_round_trips_through_archive() {
+        let format = Bnd4Flags::SUPPORTS_IDS
+            | Bnd4Flags::SUPPORTS_PATHS
+            | Bnd4Flags::LONG_OFFSETS
+            | Bnd4Flags::SUPPORTS_COMPRESSION;
+        let mut writer = Bnd4Writer::new(Cursor::new(Vec::new()), format, false, false);
+
+        writer.push(Bnd4WriterEntry {
+            id: Some(0),
+            name: Some("a.txt".to_string()),
+            data: b"hello world".to_vec(),
+            compressed: false,
+        });
+        writer.push(Bnd4WriterEntry {
+            id: Some(1),
+            name: Some("dir/b.txt".to_string()),
+            data: b"some longer payload that gets compressed".repeat(4),
+            compressed: true,
+        });
+
+        let mut cursor = writer.finish().unwrap();
+        Seek::seek(&mut cursor, SeekFrom::Start(0)).unwrap();
+        let mut archive = Bnd4Archive::new(cursor).unwrap();
+        assert_eq!(archive.len(), 2);
+
+        let mut file0 = archive.file(0).unwrap();
+        assert_eq!(file0.name(), Some("a.txt"));
+        let mut buf0 = Vec::new();
+        file0.read_to_end(&mut buf0).unwrap();
+        assert_eq!(buf0, b"hello world");
+        drop(file0);
+
+        let mut file1 = archive.file_by_name("dir/b.txt").unwrap();
+        let mut buf1 = Vec::new();
+        file1.read_to_end(&mut buf1).unwrap();
+        assert_eq!(buf1, b"some longer payload that gets compressed".repeat(4));
+    }
+
+    /// Hand-rolls a name-bucket table after a [`Bnd4Writer`]-produced archive
+    /// (the writer never emits one) so [`Bnd4Archive::find_by_name_bucket_ordered`]
+    /// can be exercised directly, including a hash collision placed ahead of
+    /// the real match: the lookup must reject it by name and keep scanning
+    /// the bucket instead of returning the wrong file.
+    #[test]
+    fn find_by_name_bucket_falls_through_hash_collisions() {
+        let format = Bnd4Flags::SUPPORTS_IDS | Bnd4Flags::SUPPORTS_PATHS | Bnd4Flags::LONG_OFFSETS;
+        let mut writer = Bnd4Writer::new(Cursor::new(Vec::new()), format, false, false);
+
+        writer.push(Bnd4WriterEntry {
+            id: Some(0),
+            name: Some("a.txt".to_string()),
+            data: b"AAAA".to_vec(),
+            compressed: false,
+        });
+        writer.push(Bnd4WriterEntry {
+            id: Some(1),
+            name: Some("b.txt".to_string()),
+            data: b"BBBB".to_vec(),
+            compressed: false,
+        });
+
+        let mut bytes = writer.finish().unwrap().into_inner();
+
+        let hash_a = hash_path("a.txt");
+        let buckets_offset = bytes.len() as u64;
+        let entries_offset = buckets_offset + 4 + 12;
+
+        bytes.write_u32::<LittleEndian>(1).unwrap(); // bucket_count
+        bytes.write_u32::<LittleEndian>(2).unwrap(); // bucket 0: entry_count
+        bytes.write_u64::<LittleEndian>(entries_offset).unwrap();
+
+        // Decoy: a.txt's hash, but pointing at entry 1 (b.txt).
+        bytes.write_u32::<LittleEndian>(hash_a).unwrap();
+        bytes.write_u32::<LittleEndian>(1).unwrap();
+        // The real a.txt entry.
+        bytes.write_u32::<LittleEndian>(hash_a).unwrap();
+        bytes.write_u32::<LittleEndian>(0).unwrap();
+
+        let header_end = ARCHIVE_HEADER_SIZE as usize;
+        bytes[(header_end - 8)..header_end].copy_from_slice(&buckets_offset.to_le_bytes());
+
+        let mut archive = Bnd4Archive::new(Cursor::new(bytes)).unwrap();
+        assert_eq!(archive.archive_info.name_buckets_offset, buckets_offset);
+
+        let mut file = archive.file_by_name("a.txt").unwrap();
+        let mut buf = Vec::new();
+        file.read_to_end(&mut buf).unwrap();
+        assert_eq!(buf, b"AAAA");
+    }
+}
@@ -0,0 +1,2 @@
+pub mod dcx;
+pub use dcx::*;
@@ -1,9 +1,18 @@
-use std::io::Read;
+use std::cmp::Ordering;
+use std::io::{Read, Seek, SeekFrom, Write};
 use std::mem::size_of;
 
-use byteorder::BigEndian;
+use byteorder::{BigEndian, ReadBytesExt};
 use flate2::read::ZlibDecoder;
-use zerocopy::{AsBytes, ByteSlice, FromBytes, LayoutVerified, Unaligned, U32};
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use zerocopy::{AsBytes, FromBytes, LayoutVerified, U32};
+
+#[cfg(feature = "compress-zstd")]
+use std::io::BufReader;
+
+use crate::io::ReadExt;
+use crate::Error;
 
 #[derive(FromBytes, AsBytes, Debug)]
 #[repr(C)]
@@ -26,30 +35,82 @@ pub struct Metadata {
 
 pub struct DcxReader<R: Read> {
     codec: DcxCompressionCodec<R>,
+    size: u64,
 }
 
 pub enum DcxCompressionCodec<R: Read> {
     Deflate(ZlibDecoder<R>),
+    #[cfg(feature = "compress-zstd")]
+    Zstd(zstd::stream::read::Decoder<'static, BufReader<R>>),
+}
+
+/// Fixed byte offsets of `Metadata`'s magic fields, for [`Error::BadMagic`]
+/// diagnostics. `DcxReader` only requires `R: Read`, so these can't be read
+/// back from the stream with `Seek` — they're static given the header's
+/// fixed `repr(C)` layout.
+const DCX_MAGIC_OFFSET: u64 = 0;
+const DCS_MAGIC_OFFSET: u64 = 24;
+const DCP_MAGIC_OFFSET: u64 = 36;
+
+fn check_magic(found: &[u8; 4], expected: &[u8; 4], offset: u64) -> Result<(), Error> {
+    if found != expected {
+        return Err(Error::BadMagic {
+            expected: expected.to_vec(),
+            found: found.to_vec(),
+            offset,
+        });
+    }
+
+    Ok(())
 }
 
 impl<R: Read> DcxReader<R> {
-    pub fn new(mut reader: R) -> Result<Self, std::io::Error> {
+    pub fn new(mut reader: R) -> Result<Self, Error> {
         let mut header_buffer = [0u8; size_of::<Metadata>()];
         reader.read_exact(&mut header_buffer)?;
 
-        let header = LayoutVerified::<_, Metadata>::new(&header_buffer[..]).unwrap();
-        assert_eq!(&header.dcx_magic, b"DCX\0");
-        assert_eq!(&header.dcp_magic, b"DCP\0");
-        assert_eq!(&header.dcs_magic, b"DCS\0");
+        let header = LayoutVerified::<_, Metadata>::new(&header_buffer[..])
+            .ok_or_else(|| crate::io::invalid_data("Malformed DCX metadata"))?;
+
+        check_magic(&header.dcx_magic, b"DCX\0", DCX_MAGIC_OFFSET)?;
+        check_magic(&header.dcp_magic, b"DCP\0", DCP_MAGIC_OFFSET)?;
+        check_magic(&header.dcs_magic, b"DCS\0", DCS_MAGIC_OFFSET)?;
+
+        let size = header.size.get() as u64;
 
         let codec = match &header.algorithm {
             b"DFLT" => DcxCompressionCodec::Deflate(ZlibDecoder::new(reader)),
-            b"KRAK" => unimplemented!("Oodle Kraken"),
-            b"EDGE" => unimplemented!("Edge?"),
-            _ => unimplemented!(),
+            // Oodle Kraken isn't implemented yet; report it like any other
+            // unsupported codec instead of aborting the process.
+            b"KRAK" => return Err(Error::UnsupportedCodec(header.algorithm)),
+            b"EDGE" => {
+                return Err(crate::io::invalid_data(
+                    "EDGE compressed DCX is chunked and seekable; use EdgeDcxReader instead",
+                ))
+            }
+            #[cfg(feature = "compress-zstd")]
+            b"ZSTD" => DcxCompressionCodec::Zstd(
+                zstd::stream::read::Decoder::new(reader)
+                    .map_err(|err| crate::io::invalid_data(&err.to_string()))?,
+            ),
+            #[cfg(not(feature = "compress-zstd"))]
+            b"ZSTD" => {
+                return Err(crate::io::invalid_data(
+                    "DCX uses ZSTD compression, but the `compress-zstd` feature is not enabled",
+                ))
+            }
+            _ => return Err(Error::UnsupportedCodec(header.algorithm)),
         };
 
-        Ok(Self { codec })
+        Ok(Self { codec, size })
+    }
+
+    /// Wraps this reader so that exhausting it checks the total bytes
+    /// produced against `Metadata.size`, surfacing a short read as an
+    /// `InvalidData` error instead of silently truncating.
+    pub fn verify(self) -> crate::io::VerifyingReader<Self> {
+        let size = self.size;
+        crate::io::VerifyingReader::new(self, size)
     }
 }
 
@@ -57,7 +118,406 @@ impl<R: Read> Read for DcxReader<R> {
     fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
         match &mut self.codec {
             DcxCompressionCodec::Deflate(compressor) => compressor.read(buf),
-            _ => unimplemented!(),
+            #[cfg(feature = "compress-zstd")]
+            DcxCompressionCodec::Zstd(compressor) => compressor.read(buf),
+        }
+    }
+}
+
+/// Decompressed size of an individual EDGE chunk, as produced by FromSoftware's
+/// EDGE encoder. Every chunk decompresses to this many bytes except (possibly)
+/// the last, which holds whatever remains of `Metadata.size`.
+const EDGE_CHUNK_SIZE: u64 = 0x10000;
+
+struct EdgeChunk {
+    data_offset: u64,
+    comp_size: u32,
+    uncomp_offset: u64,
+    uncomp_size: u32,
+}
+
+/// Reads EDGE-compressed DCX containers (Dark Souls: Prepare to Die Edition and
+/// similar), which hold an `EgdT` block table followed by many independently
+/// zlib-compressed chunks rather than one contiguous deflate stream.
+///
+/// Unlike [`DcxReader`], this requires a seekable source: chunks are inflated
+/// lazily. `Seek` only records the target position; the next read
+/// binary-searches the block index for the chunk covering it.
+pub struct EdgeDcxReader<R: Read + Seek> {
+    reader: R,
+    chunks: Vec<EdgeChunk>,
+    size: u64,
+    position: u64,
+    current_chunk: Option<usize>,
+    current_data: Vec<u8>,
+}
+
+impl<R: Read + Seek> EdgeDcxReader<R> {
+    pub fn new(mut reader: R) -> Result<Self, Error> {
+        let mut header_buffer = [0u8; size_of::<Metadata>()];
+        reader.read_exact(&mut header_buffer)?;
+
+        let header = LayoutVerified::<_, Metadata>::new(&header_buffer[..])
+            .ok_or_else(|| crate::io::invalid_data("Malformed DCX metadata"))?;
+
+        check_magic(&header.dcx_magic, b"DCX\0", DCX_MAGIC_OFFSET)?;
+        check_magic(&header.dcp_magic, b"DCP\0", DCP_MAGIC_OFFSET)?;
+        check_magic(&header.dcs_magic, b"DCS\0", DCS_MAGIC_OFFSET)?;
+
+        if &header.algorithm != b"EDGE" {
+            return Err(Error::UnsupportedCodec(header.algorithm));
+        }
+        let size = header.size.get() as u64;
+
+        reader.expect(b"EgdT")?;
+        let _version = reader.read_u32::<BigEndian>()?;
+        let chunk_count = reader.read_u32::<BigEndian>()?;
+
+        let mut chunks = Vec::with_capacity(chunk_count as usize);
+        let mut uncomp_offset = 0u64;
+        for _ in 0..chunk_count {
+            let _unk = reader.read_u32::<BigEndian>()?;
+            let chunk_offset = reader.read_u32::<BigEndian>()? as u64;
+            let comp_size = reader.read_u32::<BigEndian>()?;
+            let _flag = reader.read_u32::<BigEndian>()?;
+
+            let uncomp_size = EDGE_CHUNK_SIZE.min(size - uncomp_offset) as u32;
+            chunks.push(EdgeChunk {
+                data_offset: chunk_offset,
+                comp_size,
+                uncomp_offset,
+                uncomp_size,
+            });
+            uncomp_offset += uncomp_size as u64;
+        }
+
+        if uncomp_offset != size {
+            return Err(crate::io::invalid_data(
+                "EDGE chunk decompressed sizes do not sum to Metadata.size",
+            ));
+        }
+
+        // Chunk offsets in the table are relative to the start of the
+        // compressed data region, which immediately follows the block table.
+        let data_start = reader.stream_position()?;
+        for chunk in &mut chunks {
+            chunk.data_offset += data_start;
+        }
+
+        Ok(Self {
+            reader,
+            chunks,
+            size,
+            position: 0,
+            current_chunk: None,
+            current_data: Vec::new(),
+        })
+    }
+
+    /// Wraps this reader so that exhausting it checks the total bytes
+    /// produced against `Metadata.size`, mirroring [`DcxReader::verify`].
+    pub fn verify(self) -> crate::io::VerifyingReader<Self> {
+        let size = self.size;
+        crate::io::VerifyingReader::new(self, size)
+    }
+
+    fn chunk_index_for(&self, position: u64) -> usize {
+        match self.chunks.binary_search_by(|chunk| {
+            if position < chunk.uncomp_offset {
+                Ordering::Greater
+            } else if position >= chunk.uncomp_offset + chunk.uncomp_size as u64 {
+                Ordering::Less
+            } else {
+                Ordering::Equal
+            }
+        }) {
+            Ok(index) => index,
+            Err(index) => index.min(self.chunks.len().saturating_sub(1)),
+        }
+    }
+
+    fn load_chunk(&mut self, index: usize) -> std::io::Result<()> {
+        if self.current_chunk == Some(index) {
+            return Ok(());
+        }
+
+        let chunk = &self.chunks[index];
+        self.reader.seek(SeekFrom::Start(chunk.data_offset))?;
+
+        let mut compressed = vec![0u8; chunk.comp_size as usize];
+        self.reader.read_exact(&mut compressed)?;
+
+        let mut decompressed = vec![0u8; chunk.uncomp_size as usize];
+        ZlibDecoder::new(&compressed[..]).read_exact(&mut decompressed)?;
+
+        self.current_chunk = Some(index);
+        self.current_data = decompressed;
+        Ok(())
+    }
+}
+
+impl<R: Read + Seek> Read for EdgeDcxReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.position >= self.size {
+            return Ok(0);
+        }
+
+        let index = self.chunk_index_for(self.position);
+        self.load_chunk(index)?;
+
+        let chunk = &self.chunks[index];
+        let offset_in_chunk = (self.position - chunk.uncomp_offset) as usize;
+        let available = &self.current_data[offset_in_chunk..];
+
+        let n = available.len().min(buf.len());
+        buf[..n].copy_from_slice(&available[..n]);
+        self.position += n as u64;
+
+        Ok(n)
+    }
+}
+
+impl<R: Read + Seek> Seek for EdgeDcxReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        let new_position = match pos {
+            SeekFrom::Start(offset) => offset as i64,
+            SeekFrom::End(offset) => self.size as i64 + offset,
+            SeekFrom::Current(offset) => self.position as i64 + offset,
+        };
+
+        if new_position < 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "Cannot seek to a negative position",
+            ));
         }
+
+        self.position = new_position as u64;
+        Ok(self.position)
+    }
+}
+
+/// Writes a deflate-compressed DCX container, the inverse of [`DcxReader`].
+///
+/// Buffers the uncompressed payload written through `Write` and only produces
+/// the `Metadata` header and compressed bytes once [`DcxWriter::finish`] is
+/// called, since the header needs `compressed_size`/`size` up front and `W`
+/// isn't required to be seekable.
+pub struct DcxWriter<W: Write> {
+    writer: W,
+    uncompressed: Vec<u8>,
+}
+
+impl<W: Write> DcxWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            uncompressed: Vec::new(),
+        }
+    }
+
+    pub fn finish(mut self) -> Result<W, Error> {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&self.uncompressed)?;
+        let compressed = encoder.finish()?;
+
+        let header = Metadata {
+            dcx_magic: *b"DCX\0",
+            format_magic: *b"DCX\0",
+            dcs_offset: U32::new(0x18),
+            dcp_offset: U32::new(0x24),
+            unk1: U32::new(0x24),
+            unk2: U32::new(0x24),
+            dcs_magic: *b"DCS\0",
+            compressed_size: U32::new(compressed.len() as u32),
+            size: U32::new(self.uncompressed.len() as u32),
+            dcp_magic: *b"DCP\0",
+            algorithm: *b"DFLT",
+            unk3: [0x20, 0x9000000, 0, 0, 0, 0x10000],
+            dca_magic: *b"DCA\0",
+            // Vanilla DCX files always report a fixed 8-byte DCA chunk.
+            dca_size: U32::new(0x08),
+        };
+
+        self.writer.write_all(header.as_bytes())?;
+        self.writer.write_all(&compressed)?;
+        Ok(self.writer)
+    }
+}
+
+impl<W: Write> Write for DcxWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.uncompressed.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async")]
+use async_compression::tokio::bufread::ZlibDecoder as AsyncZlibDecoder;
+#[cfg(feature = "async")]
+use tokio::io::{AsyncRead, AsyncReadExt, BufReader as AsyncBufReader, ReadBuf};
+
+#[cfg(feature = "async")]
+pin_project_lite::pin_project! {
+    /// Async counterpart to [`DcxReader`], for sources that only expose
+    /// [`tokio::io::AsyncRead`]. Shares `Metadata` parsing and magic checks
+    /// with the sync reader; only deflate is supported for now, since that's
+    /// the only codec the `async-compression` dependency is pulled in for.
+    pub struct AsyncDcxReader<R: AsyncRead> {
+        #[pin]
+        inner: AsyncZlibDecoder<AsyncBufReader<R>>,
+        size: u64,
+    }
+}
+
+#[cfg(feature = "async")]
+impl<R: AsyncRead + Unpin> AsyncDcxReader<R> {
+    pub async fn new(mut reader: R) -> Result<Self, Error> {
+        let mut header_buffer = [0u8; size_of::<Metadata>()];
+        reader.read_exact(&mut header_buffer).await?;
+
+        let header = LayoutVerified::<_, Metadata>::new(&header_buffer[..])
+            .ok_or_else(|| crate::io::invalid_data("Malformed DCX metadata"))?;
+
+        check_magic(&header.dcx_magic, b"DCX\0", DCX_MAGIC_OFFSET)?;
+        check_magic(&header.dcp_magic, b"DCP\0", DCP_MAGIC_OFFSET)?;
+        check_magic(&header.dcs_magic, b"DCS\0", DCS_MAGIC_OFFSET)?;
+
+        if &header.algorithm != b"DFLT" {
+            return Err(Error::UnsupportedCodec(header.algorithm));
+        }
+
+        let size = header.size.get() as u64;
+        let inner = AsyncZlibDecoder::new(AsyncBufReader::new(reader));
+
+        Ok(Self { inner, size })
+    }
+
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+}
+
+#[cfg(feature = "async")]
+impl<R: AsyncRead> AsyncRead for AsyncDcxReader<R> {
+    fn poll_read(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        self.project().inner.poll_read(cx, buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use byteorder::WriteBytesExt;
+
+    use super::*;
+
+    #[test]
+    fn dcx_writer_round_trips_through_reader() {
+        let payload = b"the quick brown fox jumps over the lazy dog".repeat(100);
+
+        let mut writer = DcxWriter::new(Vec::new());
+        writer.write_all(&payload).unwrap();
+        let bytes = writer.finish().unwrap();
+
+        let mut reader = DcxReader::new(Cursor::new(bytes)).unwrap().verify();
+        let mut output = Vec::new();
+        reader.read_to_end(&mut output).unwrap();
+
+        assert_eq!(output, payload);
+    }
+
+    /// Builds a hand-rolled EDGE-compressed DCX container out of independently
+    /// zlib-compressed chunks, mirroring the layout [`EdgeDcxReader::new`]
+    /// expects: a `Metadata` header, then an `EgdT` block table, then the
+    /// chunks themselves back to back.
+    fn build_edge_dcx(chunks: &[Vec<u8>]) -> Vec<u8> {
+        let total_size: u32 = chunks.iter().map(|c| c.len() as u32).sum();
+
+        let compressed_chunks: Vec<Vec<u8>> = chunks
+            .iter()
+            .map(|chunk| {
+                let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(chunk).unwrap();
+                encoder.finish().unwrap()
+            })
+            .collect();
+
+        let header = Metadata {
+            dcx_magic: *b"DCX\0",
+            format_magic: *b"DCX\0",
+            dcs_offset: U32::new(0x18),
+            dcp_offset: U32::new(0x24),
+            unk1: U32::new(0x24),
+            unk2: U32::new(0x24),
+            dcs_magic: *b"DCS\0",
+            compressed_size: U32::new(0),
+            size: U32::new(total_size),
+            dcp_magic: *b"DCP\0",
+            algorithm: *b"EDGE",
+            unk3: [0; 6],
+            dca_magic: *b"DCA\0",
+            dca_size: U32::new(0x08),
+        };
+
+        let mut bytes = header.as_bytes().to_vec();
+        bytes.extend_from_slice(b"EgdT");
+        bytes.write_u32::<BigEndian>(0).unwrap(); // version
+        bytes.write_u32::<BigEndian>(chunks.len() as u32).unwrap();
+
+        let mut data_offset = 0u32;
+        for compressed in &compressed_chunks {
+            bytes.write_u32::<BigEndian>(0).unwrap(); // unk
+            bytes.write_u32::<BigEndian>(data_offset).unwrap();
+            bytes
+                .write_u32::<BigEndian>(compressed.len() as u32)
+                .unwrap();
+            bytes.write_u32::<BigEndian>(0).unwrap(); // flag
+            data_offset += compressed.len() as u32;
+        }
+
+        for compressed in &compressed_chunks {
+            bytes.extend_from_slice(compressed);
+        }
+
+        bytes
+    }
+
+    #[test]
+    fn edge_dcx_reads_and_seeks_across_chunks() {
+        let first_chunk: Vec<u8> = (0..EDGE_CHUNK_SIZE as usize)
+            .map(|i| (i % 251) as u8)
+            .collect();
+        let second_chunk: Vec<u8> = (0..4096usize).map(|i| ((i * 7) % 251) as u8).collect();
+        let expected: Vec<u8> = first_chunk
+            .iter()
+            .chain(second_chunk.iter())
+            .copied()
+            .collect();
+
+        let bytes = build_edge_dcx(&[first_chunk, second_chunk]);
+        let mut reader = EdgeDcxReader::new(Cursor::new(bytes)).unwrap();
+
+        let mut all = Vec::new();
+        reader.read_to_end(&mut all).unwrap();
+        assert_eq!(all, expected);
+
+        // Seek back into the first chunk, then read across the chunk
+        // boundary to make sure `chunk_index_for`/`load_chunk` agree.
+        reader
+            .seek(SeekFrom::Start(EDGE_CHUNK_SIZE - 10))
+            .unwrap();
+        let mut tail = Vec::new();
+        reader.read_to_end(&mut tail).unwrap();
+        assert_eq!(tail, expected[(EDGE_CHUNK_SIZE as usize - 10)..]);
     }
 }
@@ -0,0 +1,31 @@
+use thiserror::Error;
+
+/// Crate-wide error type. Every parser in this crate returns this instead of
+/// panicking, asserting, or unwrapping on malformed input — the files this
+/// crate reads come from untrusted, often modded, game archives.
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("bad magic at offset {offset}: expected {expected:?}, found {found:?}")]
+    BadMagic {
+        expected: Vec<u8>,
+        found: Vec<u8>,
+        offset: u64,
+    },
+
+    #[error("unsupported DCX compression codec {0:?}")]
+    UnsupportedCodec([u8; 4]),
+
+    #[error("index {index} out of range (archive has {len} entries)")]
+    IndexOutOfRange { index: usize, len: usize },
+
+    #[error("{0}")]
+    InvalidData(String),
+
+    #[error("{0}")]
+    NotFound(String),
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
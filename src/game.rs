@@ -0,0 +1,2 @@
+//! Per-game metadata (file extensions, archive layouts, etc.) is not yet
+//! implemented; this module is reserved for that.
@@ -1,23 +1,26 @@
-use std::io::{Error, ErrorKind, Read, Seek, SeekFrom};
+use std::io::{Error as IoError, ErrorKind, Read, Seek, SeekFrom, Write};
 
 use byteorder::{ByteOrder, ReadBytesExt};
 use encoding_rs::{SHIFT_JIS, UTF_16BE, UTF_16LE};
 
+use crate::Error;
+
 pub fn invalid_data(message: &str) -> Error {
-    Error::new(ErrorKind::InvalidData, message)
+    Error::InvalidData(message.to_string())
+}
+
+pub fn not_found(message: &str) -> Error {
+    Error::NotFound(message.to_string())
 }
 
 fn expect_num<R: Read + ?Sized, T: Sized + PartialEq>(
     reader: &mut R,
-    read_fn: fn(&mut R) -> Result<T, Error>,
+    read_fn: fn(&mut R) -> Result<T, IoError>,
     value: T,
 ) -> Result<(), Error> {
     match read_fn(reader)? {
         v if v == value => Ok(()),
-        _ => Err(Error::new(
-            ErrorKind::InvalidData,
-            "Unexpected data".to_string(),
-        )),
+        _ => Err(invalid_data("Unexpected data")),
     }
 }
 
@@ -36,6 +39,26 @@ pub trait SeekableReadExt: Read + Seek {
 
         result
     }
+
+    /// Like [`ReadExt::expect`], but records the current stream offset so
+    /// callers get an actionable diagnostic instead of a bare mismatch.
+    #[inline]
+    fn expect_magic_at_offset(&mut self, expected: &[u8]) -> Result<(), Error> {
+        let offset = self.stream_position()?;
+
+        let mut found = vec![0u8; expected.len()];
+        self.read_exact(&mut found)?;
+
+        if found != expected {
+            return Err(Error::BadMagic {
+                expected: expected.to_vec(),
+                found,
+                offset,
+            });
+        }
+
+        Ok(())
+    }
 }
 
 pub trait ReadExt: Read {
@@ -53,10 +76,7 @@ pub trait ReadExt: Read {
     fn expect(&mut self, bytes: &[u8]) -> Result<bool, Error> {
         for (pos, byte) in bytes.iter().enumerate() {
             if self.read_u8()? != *byte {
-                return Err(Error::new(
-                    ErrorKind::InvalidData,
-                    format!("Unexpected data at position {}", pos),
-                ));
+                return Err(invalid_data(&format!("Unexpected data at position {}", pos)));
             }
         }
 
@@ -65,7 +85,7 @@ pub trait ReadExt: Read {
 
     #[inline]
     fn read_bool(&mut self) -> Result<bool, Error> {
-        self.read_u8().map(|v| v == 1)
+        Ok(self.read_u8()? == 1)
     }
 
     #[inline]
@@ -78,8 +98,7 @@ pub trait ReadExt: Read {
             }
         }
 
-        let (decoded, ..) = SHIFT_JIS.decode(&data[..]);
-        Ok(decoded.to_string())
+        Ok(decode_name(&data, false, false))
     }
 
     #[inline]
@@ -95,15 +114,186 @@ pub trait ReadExt: Read {
             }
         }
 
+        Ok(decode_name(&data, true, is_big_endian))
+    }
+
+    /// Reads a null-terminated name, dispatching between Shift-JIS
+    /// ([`ReadExt::read_cstr`]) and UTF-16 ([`ReadExt::read_utf16`]) the same
+    /// way every BND4 name field does: based on the archive's `unicode` flag.
+    #[inline]
+    fn read_name(&mut self, is_unicode: bool, is_big_endian: bool) -> Result<String, Error> {
+        if is_unicode {
+            self.read_utf16(is_big_endian)
+        } else {
+            self.read_cstr()
+        }
+    }
+}
+
+impl<R: Read + ?Sized> ReadExt for R {}
+impl<R: Read + Seek + ?Sized> SeekableReadExt for R {}
+
+/// Decodes a name's raw bytes the way BND4 name tables encode them: Shift-JIS
+/// for ASCII-era archives, UTF-16 (LE or BE per `is_big_endian`) when
+/// `is_unicode` is set. Pulled out of [`ReadExt::read_cstr`]/[`read_utf16`]
+/// so any caller that has already collected the raw bytes (e.g. an async
+/// reader that can't use the blocking `ReadExt` trait) decodes identically.
+pub(crate) fn decode_name(bytes: &[u8], is_unicode: bool, is_big_endian: bool) -> String {
+    if is_unicode {
         let (name, ..) = if is_big_endian {
-            UTF_16BE.decode(&data[..])
+            UTF_16BE.decode(bytes)
         } else {
-            UTF_16LE.decode(&data[..])
+            UTF_16LE.decode(bytes)
         };
+        name.to_string()
+    } else {
+        let (name, ..) = SHIFT_JIS.decode(bytes);
+        name.to_string()
+    }
+}
+
+/// Wraps a reader that is expected to produce exactly `expected_size` bytes,
+/// surfacing a mismatch as an `InvalidData` error once the inner reader hits
+/// EOF instead of silently yielding a truncated read.
+///
+/// With the `checksum` feature enabled it can also accumulate a running
+/// CRC32 of everything read, so callers can detect corruption that happens
+/// to decompress to the right length.
+pub struct VerifyingReader<R: Read> {
+    inner: R,
+    bytes_read: u64,
+    expected_size: u64,
+    #[cfg(feature = "checksum")]
+    hasher: crc32fast::Hasher,
+}
 
-        Ok(name.to_string())
+impl<R: Read> VerifyingReader<R> {
+    pub fn new(inner: R, expected_size: u64) -> Self {
+        Self {
+            inner,
+            bytes_read: 0,
+            expected_size,
+            #[cfg(feature = "checksum")]
+            hasher: crc32fast::Hasher::new(),
+        }
+    }
+
+    #[cfg(feature = "checksum")]
+    pub fn checksum(&self) -> u32 {
+        self.hasher.clone().finalize()
     }
 }
 
-impl<R: Read + ?Sized> ReadExt for R {}
-impl<R: Read + Seek + ?Sized> SeekableReadExt for R {}
+impl<R: Read> Read for VerifyingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, IoError> {
+        let n = self.inner.read(buf)?;
+        self.bytes_read += n as u64;
+
+        #[cfg(feature = "checksum")]
+        self.hasher.update(&buf[..n]);
+
+        if n == 0 && !buf.is_empty() && self.bytes_read != self.expected_size {
+            return Err(IoError::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "Decompressed size mismatch: expected {} bytes, got {}",
+                    self.expected_size, self.bytes_read
+                ),
+            ));
+        }
+
+        Ok(n)
+    }
+}
+
+/// Converts a value to its endian-specific byte representation, mirroring the
+/// `O: ByteOrder` generic used throughout [`ReadExt`] so reads and writes stay
+/// symmetric.
+pub trait ToBytes: Sized {
+    type Bytes: AsRef<[u8]>;
+
+    fn to_bytes<O: ByteOrder>(self) -> Self::Bytes;
+}
+
+impl ToBytes for u16 {
+    type Bytes = [u8; 2];
+
+    #[inline]
+    fn to_bytes<O: ByteOrder>(self) -> [u8; 2] {
+        let mut bytes = [0u8; 2];
+        O::write_u16(&mut bytes, self);
+        bytes
+    }
+}
+
+impl ToBytes for i32 {
+    type Bytes = [u8; 4];
+
+    #[inline]
+    fn to_bytes<O: ByteOrder>(self) -> [u8; 4] {
+        let mut bytes = [0u8; 4];
+        O::write_i32(&mut bytes, self);
+        bytes
+    }
+}
+
+impl ToBytes for u32 {
+    type Bytes = [u8; 4];
+
+    #[inline]
+    fn to_bytes<O: ByteOrder>(self) -> [u8; 4] {
+        let mut bytes = [0u8; 4];
+        O::write_u32(&mut bytes, self);
+        bytes
+    }
+}
+
+impl ToBytes for u64 {
+    type Bytes = [u8; 8];
+
+    #[inline]
+    fn to_bytes<O: ByteOrder>(self) -> [u8; 8] {
+        let mut bytes = [0u8; 8];
+        O::write_u64(&mut bytes, self);
+        bytes
+    }
+}
+
+pub trait WriteExt: Write {
+    #[inline]
+    fn write_num<T: ToBytes, O: ByteOrder>(&mut self, value: T) -> Result<(), Error> {
+        self.write_all(value.to_bytes::<O>().as_ref())?;
+        Ok(())
+    }
+
+    #[inline]
+    fn write_bool(&mut self, value: bool) -> Result<(), Error> {
+        self.write_all(&[value as u8])?;
+        Ok(())
+    }
+
+    #[inline]
+    fn write_cstr(&mut self, value: &str) -> Result<(), Error> {
+        let (encoded, ..) = SHIFT_JIS.encode(value);
+        self.write_all(&encoded)?;
+        self.write_all(&[0])?;
+        Ok(())
+    }
+
+    #[inline]
+    fn write_utf16(&mut self, value: &str, is_big_endian: bool) -> Result<(), Error> {
+        for unit in value.encode_utf16() {
+            let bytes = if is_big_endian {
+                unit.to_be_bytes()
+            } else {
+                unit.to_le_bytes()
+            };
+            self.write_all(&bytes)?;
+        }
+
+        self.write_all(&[0, 0])?;
+        Ok(())
+    }
+}
+
+impl<W: Write + ?Sized> WriteExt for W {}
@@ -7,4 +7,7 @@ pub use container::*;
 
 pub mod game;
 
+mod error;
+pub use error::*;
+
 mod io;